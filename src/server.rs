@@ -1,9 +1,10 @@
 use hyper::body::Incoming as IncomingBody;
 use hyper::{Request, Response};
-use hyper::server::conn::http1;
+use hyper::server::conn::{http1, http2};
 use hyper::service::service_fn;
-use hyper_util::rt::TokioIo;
-use tokio::net::TcpListener;
+use hyper_util::rt::{TokioIo, TokioExecutor};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::io::AsyncReadExt;
 use http_body_util::{Full, BodyExt};
 use bytes::Bytes;
 use pyo3::prelude::*;
@@ -19,14 +20,636 @@ use std::collections::HashMap as StdHashMap;
 use crate::zerocopy::ZeroCopyBufferPool;
 use std::time::{Duration, Instant};
 use std::thread;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::UnixListener;
+use flate2::write::{DeflateEncoder, GzEncoder};
+use flate2::Compression;
+use std::io::Write;
+use thiserror::Error;
+use socket2::{Domain, Protocol, Socket, TcpKeepalive, Type};
+use std::os::unix::io::AsRawFd;
+use pyo3::exceptions::PyRuntimeError;
+use tracing::Subscriber;
+use tracing::field::{Field, Visit};
+use std::sync::atomic::AtomicU64;
 
 type Handler = Arc<PyObject>;
 
+// ============================================================================
+// HANDLER ERRORS - typed results in place of Result<String, String>
+// ============================================================================
+
+/// What a successful handler call produced: the serialized body plus
+/// whatever the handler (or a future typed-response wrapper) wants to
+/// override on the HTTP response.
+#[derive(Debug, Clone)]
+struct HandlerResponse {
+    body: String,
+    content_type: Option<String>,
+    status: Option<u16>,
+    /// Extra response headers the handler (or a wrapper like `Promise`'s
+    /// ack) wants set. Empty for the common JSON-body case today, but this
+    /// is what `response_is_cacheable` inspects for `Set-Cookie`/`Vary`.
+    headers: Vec<(String, String)>,
+}
+
+impl HandlerResponse {
+    fn json(body: String) -> Self {
+        Self { body, content_type: None, status: None, headers: Vec::new() }
+    }
+}
+
+/// Typed handler failures, replacing the old `Result<String, String>` +
+/// substring matching on the error message. Each variant maps to exactly
+/// one HTTP status via `status_code()`.
+#[derive(Error, Debug, Clone)]
+enum HandlerError {
+    #[error("validation error on '{field}': {msg}")]
+    Validation { field: String, msg: String },
+    #[error("not found")]
+    NotFound,
+    #[error("request timed out")]
+    Timeout,
+    #[error("no worker available")]
+    WorkerUnavailable,
+    #[error("serialization error: {0}")]
+    Serialization(String),
+    #[error("{0}")]
+    Python(String),
+}
+
+impl HandlerError {
+    fn status_code(&self) -> u16 {
+        match self {
+            HandlerError::Validation { .. } => 400,
+            HandlerError::NotFound => 404,
+            HandlerError::Timeout => 408,
+            HandlerError::WorkerUnavailable => 503,
+            HandlerError::Serialization(_) => 500,
+            HandlerError::Python(_) => 500,
+        }
+    }
+
+    fn error_type(&self) -> &'static str {
+        match self {
+            HandlerError::Validation { .. } => "ValidationError",
+            HandlerError::NotFound => "NotFoundError",
+            HandlerError::Timeout => "TimeoutError",
+            HandlerError::WorkerUnavailable => "WorkerUnavailable",
+            HandlerError::Serialization(_) => "SerializationError",
+            HandlerError::Python(_) => "InternalServerError",
+        }
+    }
+
+    /// Render the JSON error body returned to the client.
+    fn to_json(&self, method: &str, path: &str) -> String {
+        format!(
+            r#"{{"error": "{}", "message": "{}", "method": "{}", "path": "{}", "timestamp": {}}}"#,
+            self.error_type(),
+            self.to_string().chars().take(200).collect::<String>(),
+            method,
+            path,
+            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
+        )
+    }
+}
+
+/// Classify a Python exception by its *type*, not its message, so handlers
+/// can raise `ValidationError`/`TimeoutError`/etc. and get a deterministic
+/// HTTP status back instead of relying on substring matches in the message.
+fn classify_py_err(py: Python, err: &pyo3::PyErr) -> HandlerError {
+    let type_name = err.get_type(py).name().map(|n| n.to_string()).unwrap_or_default();
+    match type_name.as_str() {
+        "ValidationError" => HandlerError::Validation {
+            // The raising code is expected to set `.field` on the exception
+            // instance (e.g. `raise ValidationError("...", field="email")`);
+            // fall back to "unknown" only if it didn't, rather than always
+            // discarding whatever field actually failed.
+            field: err.value(py)
+                .getattr("field")
+                .and_then(|f| f.extract::<String>())
+                .unwrap_or_else(|_| "unknown".to_string()),
+            msg: err.value(py).to_string(),
+        },
+        "TimeoutError" => HandlerError::Timeout,
+        // Deliberately only the user-defined `NotFoundError`, not a bare
+        // `KeyError` — a dict-indexing bug unrelated to "resource not
+        // found" has no business being coerced into a client-facing 404.
+        "NotFoundError" => HandlerError::NotFound,
+        _ => HandlerError::Python(err.to_string()),
+    }
+}
+
+// ============================================================================
+// PLUGGABLE LISTENERS - TCP and Unix domain sockets through one code path
+// ============================================================================
+
+/// Marker trait tying together the bounds `serve_connection` needs from an
+/// accepted stream, so `Listener::accept` can hand back a single boxed type
+/// regardless of transport (TCP today, Unix sockets below, TLS later).
+trait AsyncStream: AsyncRead + AsyncWrite + Send + Unpin {}
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> AsyncStream for T {}
+
+/// Where a connection came from, for `extract_client_ip` to fall back on when
+/// the request carries no `X-Forwarded-For`/`X-Real-IP` headers.
+#[derive(Clone, Copy, Debug)]
+enum PeerAddr {
+    Tcp(SocketAddr),
+    Unix,
+}
+
+/// Object-safe "accept connections" abstraction so `run` doesn't have to know
+/// whether it's bound to a TCP port or a filesystem socket. A future TLS
+/// listener just wraps another `Listener` and implements this trait too.
+trait Listener: Send + Sync {
+    fn accept<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = io::Result<(Box<dyn AsyncStream>, PeerAddr)>> + Send + 'a>>;
+}
+
+struct TcpBindable(TcpListener);
+
+impl Listener for TcpBindable {
+    fn accept<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = io::Result<(Box<dyn AsyncStream>, PeerAddr)>> + Send + 'a>> {
+        Box::pin(async move {
+            let (stream, addr) = self.0.accept().await?;
+            apply_accepted_socket_tuning(&stream);
+            Ok((Box::new(stream) as Box<dyn AsyncStream>, PeerAddr::Tcp(addr)))
+        })
+    }
+}
+
+/// Apply `SOCKET_CONFIG`'s per-connection knobs (`TCP_NODELAY`, keep-alive)
+/// to a freshly accepted TCP stream, then opportunistically sample
+/// `TCP_INFO` into `CONNECTION_STATS` so `server_stats()` has something to
+/// report. A no-op when `configure_socket` was never called.
+fn apply_accepted_socket_tuning(stream: &TcpStream) {
+    let Some(config) = SOCKET_CONFIG.get() else { return };
+
+    let _ = stream.set_nodelay(config.nodelay);
+
+    if let Some(idle_secs) = config.keepalive_idle_secs {
+        let mut keepalive = TcpKeepalive::new().with_time(Duration::from_secs(idle_secs as u64));
+        if let Some(interval_secs) = config.keepalive_interval_secs {
+            keepalive = keepalive.with_interval(Duration::from_secs(interval_secs as u64));
+        }
+        let _ = socket2::SockRef::from(stream).set_tcp_keepalive(&keepalive);
+    }
+
+    if let Some(info) = read_tcp_info(stream.as_raw_fd()) {
+        use std::sync::atomic::Ordering;
+        CONNECTION_STATS.last_rtt_micros.store(info.rtt_micros, Ordering::Relaxed);
+        CONNECTION_STATS.last_retransmits.store(info.retransmits, Ordering::Relaxed);
+        CONNECTION_STATS.last_congestion_window.store(info.congestion_window, Ordering::Relaxed);
+    }
+}
+
+/// Unix-domain-socket listener, unlinking any stale socket file left behind
+/// by a previous run before binding and removing it again on drop.
+struct UnixBindable {
+    listener: UnixListener,
+    path: String,
+}
+
+impl Listener for UnixBindable {
+    fn accept<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = io::Result<(Box<dyn AsyncStream>, PeerAddr)>> + Send + 'a>> {
+        Box::pin(async move {
+            let (stream, _addr) = self.listener.accept().await?;
+            Ok((Box::new(stream) as Box<dyn AsyncStream>, PeerAddr::Unix))
+        })
+    }
+}
+
+impl Drop for UnixBindable {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Bind either a TCP address (`host:port`) or, when `addr_str` starts with
+/// `unix:`, a Unix domain socket at the given filesystem path.
+fn bind_listener(addr_str: &str) -> io::Result<Box<dyn Listener>> {
+    if let Some(socket_path) = addr_str.strip_prefix("unix:") {
+        // Remove a stale socket file from a previous, uncleanly-stopped run.
+        let _ = std::fs::remove_file(socket_path);
+        let listener = UnixListener::bind(socket_path)?;
+        Ok(Box::new(UnixBindable {
+            listener,
+            path: socket_path.to_string(),
+        }))
+    } else {
+        let addr: SocketAddr = addr_str
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid address"))?;
+
+        // Go through socket2 instead of std::net::TcpListener::bind so
+        // TCP Fast Open can be set on the *listening* socket before
+        // `listen()` is called; tokio's TcpListener has no hook for this.
+        let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+        socket.set_reuse_address(true)?;
+        if SOCKET_CONFIG.get().is_some_and(|c| c.fast_open) {
+            let _ = enable_tcp_fast_open(&socket);
+        }
+        socket.bind(&addr.into())?;
+        socket.listen(1024)?;
+        socket.set_nonblocking(true)?;
+
+        let std_listener: std::net::TcpListener = socket.into();
+        Ok(Box::new(TcpBindable(TcpListener::from_std(std_listener)?)))
+    }
+}
+
+/// Enable TCP Fast Open on a listening socket (Linux only: `TCP_FASTOPEN`
+/// takes the server-side pending-request queue length, not a boolean).
+/// A no-op everywhere else since Fast Open's socket option differs per
+/// platform and `configure_socket`'s `fast_open` flag is best-effort.
+#[cfg(target_os = "linux")]
+fn enable_tcp_fast_open(socket: &Socket) -> io::Result<()> {
+    const FAST_OPEN_QUEUE_LEN: libc::c_int = 256;
+    let ret = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(),
+            libc::IPPROTO_TCP,
+            libc::TCP_FASTOPEN,
+            &FAST_OPEN_QUEUE_LEN as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    if ret != 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn enable_tcp_fast_open(_socket: &Socket) -> io::Result<()> {
+    Ok(())
+}
+
+// ============================================================================
+// SOCKET TUNING - TCP Fast Open, keep-alive, and TCP_INFO health metrics
+// ============================================================================
+
+/// Low-level socket tuning, applied when binding (Fast Open) and on each
+/// accepted TCP connection (`TCP_NODELAY`, keep-alive). Defaults match the
+/// socket options that were in effect before this existed, so benchmarks
+/// only change once `configure_socket` is actually called.
+static SOCKET_CONFIG: OnceLock<SocketConfig> = OnceLock::new();
+
+#[derive(Clone, Copy)]
+struct SocketConfig {
+    fast_open: bool,
+    keepalive_idle_secs: Option<u32>,
+    keepalive_interval_secs: Option<u32>,
+    nodelay: bool,
+}
+
+impl Default for SocketConfig {
+    fn default() -> Self {
+        Self {
+            fast_open: false,
+            keepalive_idle_secs: None,
+            keepalive_interval_secs: None,
+            nodelay: false, // Leave Nagle's algorithm as the OS default until opted in
+        }
+    }
+}
+
+/// Configure socket-level tuning for the listener and its accepted
+/// connections. Call before `run()`; `keepalive_idle_secs: None` leaves
+/// keep-alive off entirely (the current default).
+#[pyfunction]
+pub fn configure_socket(
+    fast_open: bool,
+    keepalive_idle_secs: Option<u32>,
+    keepalive_interval_secs: Option<u32>,
+    nodelay: bool,
+) {
+    let config = SocketConfig {
+        fast_open,
+        keepalive_idle_secs,
+        keepalive_interval_secs,
+        nodelay,
+    };
+    let _ = SOCKET_CONFIG.set(config);
+}
+
+/// A single `TCP_INFO` read (Linux only): round-trip time, retransmit
+/// count, and congestion window, in the units the kernel reports them
+/// (microseconds and segments respectively).
+#[derive(Clone, Copy, Default)]
+struct TcpInfoSnapshot {
+    rtt_micros: u32,
+    retransmits: u32,
+    congestion_window: u32,
+}
+
+#[cfg(target_os = "linux")]
+fn read_tcp_info(raw_fd: std::os::unix::io::RawFd) -> Option<TcpInfoSnapshot> {
+    let mut info: libc::tcp_info = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::tcp_info>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockopt(
+            raw_fd,
+            libc::IPPROTO_TCP,
+            libc::TCP_INFO,
+            &mut info as *mut _ as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if ret != 0 {
+        return None;
+    }
+    Some(TcpInfoSnapshot {
+        rtt_micros: info.tcpi_rtt,
+        retransmits: info.tcpi_retransmits as u32,
+        congestion_window: info.tcpi_snd_cwnd,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_tcp_info(_raw_fd: std::os::unix::io::RawFd) -> Option<TcpInfoSnapshot> {
+    None // TCP_INFO is a Linux-specific socket option.
+}
+
+/// Process-wide connection-health counters: accept/close counts plus the
+/// most recent `TCP_INFO` sample taken when a connection is accepted.
+struct ConnectionStats {
+    total_connections: std::sync::atomic::AtomicU64,
+    active_connections: std::sync::atomic::AtomicI64,
+    last_rtt_micros: std::sync::atomic::AtomicU32,
+    last_retransmits: std::sync::atomic::AtomicU32,
+    last_congestion_window: std::sync::atomic::AtomicU32,
+}
+
+static CONNECTION_STATS: ConnectionStats = ConnectionStats {
+    total_connections: std::sync::atomic::AtomicU64::new(0),
+    active_connections: std::sync::atomic::AtomicI64::new(0),
+    last_rtt_micros: std::sync::atomic::AtomicU32::new(0),
+    last_retransmits: std::sync::atomic::AtomicU32::new(0),
+    last_congestion_window: std::sync::atomic::AtomicU32::new(0),
+};
+
+/// Keeps `CONNECTION_STATS.active_connections` accurate for the life of one
+/// accepted connection, the same RAII pattern the backpressure `_permit`
+/// already uses in `run`.
+struct ConnectionGuard;
+
+impl ConnectionGuard {
+    fn new() -> Self {
+        use std::sync::atomic::Ordering;
+        CONNECTION_STATS.total_connections.fetch_add(1, Ordering::Relaxed);
+        CONNECTION_STATS.active_connections.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        use std::sync::atomic::Ordering;
+        CONNECTION_STATS.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of `CONNECTION_STATS` as a Python dict, so operators can see
+/// real connection state (accept counts, last-sampled RTT/retransmits/cwnd)
+/// instead of the hard-coded feature blurb in `info()`.
+#[pyfunction]
+pub fn server_stats(py: Python) -> PyResult<PyObject> {
+    use std::sync::atomic::Ordering;
+    let dict = PyDict::new(py);
+    dict.set_item("total_connections", CONNECTION_STATS.total_connections.load(Ordering::Relaxed))?;
+    dict.set_item("active_connections", CONNECTION_STATS.active_connections.load(Ordering::Relaxed).max(0))?;
+    dict.set_item("last_rtt_micros", CONNECTION_STATS.last_rtt_micros.load(Ordering::Relaxed))?;
+    dict.set_item("last_retransmits", CONNECTION_STATS.last_retransmits.load(Ordering::Relaxed))?;
+    dict.set_item("last_congestion_window", CONNECTION_STATS.last_congestion_window.load(Ordering::Relaxed))?;
+    Ok(dict.into())
+}
+
+// ============================================================================
+// LOGGING - forward tracing events to a Python logging callback
+// ============================================================================
+
+/// Collects the fields of one `tracing` event into a single message string,
+/// the same way `format!("{} {}={:?} ...")` would, since the Python side
+/// just wants a formatted line plus level/target rather than structured
+/// fields.
+#[derive(Default)]
+struct MessageVisitor {
+    message: Option<String>,
+    extra_fields: Vec<String>,
+}
+
+impl MessageVisitor {
+    fn into_message(self) -> String {
+        let message = self.message.unwrap_or_default();
+        if self.extra_fields.is_empty() {
+            message
+        } else {
+            format!("{} {}", message, self.extra_fields.join(" "))
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.extra_fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+/// `tracing::Subscriber` that forwards every enabled event to a Python
+/// callable as `logger_cb(level: str, target: str, message: str)`, so a
+/// Python app can route worker lifecycle and handler-error logging through
+/// its own `logging` module instead of the unfilterable `eprintln!` this
+/// replaces. Spans aren't tracked beyond handing out ids: nothing here
+/// currently emits nested spans, only one-shot events.
+struct PyLogSubscriber {
+    callback: Arc<PyObject>,
+    max_level: tracing::Level,
+    next_span_id: AtomicU64,
+}
+
+impl Subscriber for PyLogSubscriber {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        metadata.level() <= &self.max_level
+    }
+
+    fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(self.next_span_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let level = metadata.level().as_str();
+        let target = metadata.target().to_string();
+        let message = visitor.into_message();
+        let callback = Arc::clone(&self.callback);
+
+        Python::with_gil(|py| {
+            if let Err(e) = callback.call1(py, (level, target, message)) {
+                e.print(py);
+            }
+        });
+    }
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+/// Install `logger_cb` as the process-wide `tracing` subscriber, so worker
+/// start/stop, handler errors, and rate-limit rejections all route through
+/// it instead of `eprintln!`. `debug=True` also forwards `DEBUG`-level
+/// events; otherwise only `INFO` and above are sent. Like any global
+/// subscriber, this can only be installed once per process.
+#[pyfunction]
+pub fn init_logging(logger_cb: PyObject, debug: bool) -> PyResult<()> {
+    let subscriber = PyLogSubscriber {
+        callback: Arc::new(logger_cb),
+        max_level: if debug { tracing::Level::DEBUG } else { tracing::Level::INFO },
+        next_span_id: AtomicU64::new(1),
+    };
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| PyRuntimeError::new_err(format!("logging already initialized: {}", e)))
+}
+
+/// HTTP/2 connection preface (RFC 7540 §3.5): clients using "prior
+/// knowledge" h2c send this instead of an HTTP/1.1 request line, so we can
+/// tell the two apart before any framing has been parsed.
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// How long to wait for more of the preface once what's arrived so far is
+/// still a possible prefix of it. Real prior-knowledge h2c clients write the
+/// whole 24-byte preface as one burst, so this gets hit by a client that
+/// sends nothing at all for this long after connecting (nothing read yet is
+/// trivially a prefix), or by an HTTP/1.1 request that happens to start the
+/// same way; either one is then served as HTTP/1.1.
+const PREFACE_PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Reads up to `H2_PREFACE.len()` bytes off `stream` so `run` can decide
+/// between the HTTP/2 and HTTP/1.1 paths. There's no non-destructive peek
+/// across a boxed `AsyncRead`, so the bytes are consumed here and handed
+/// back to be replayed by `PrefixedStream`.
+///
+/// Stops as soon as what's been read can no longer be a prefix of
+/// `H2_PREFACE` — true for nearly every real HTTP/1.1 request within its
+/// first few bytes — instead of always blocking for the full 24 bytes: an
+/// HTTP/1.1 request shorter than that (e.g. a bare `HEAD / HTTP/1.1\r\n\r\n`)
+/// has nothing left to send once it's on the wire, so waiting for 24 bytes
+/// unconditionally would hang this connection forever. A short timeout
+/// covers the case where what's been read so far (possibly nothing yet)
+/// still matches but no more bytes show up.
+async fn read_preface_probe(stream: &mut Box<dyn AsyncStream>) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0u8; H2_PREFACE.len()];
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = match tokio::time::timeout(PREFACE_PROBE_TIMEOUT, stream.read(&mut buf[filled..])).await {
+            Ok(result) => result?,
+            Err(_) => break, // No more bytes within the timeout — not a preface.
+        };
+        if n == 0 {
+            break; // Peer disconnected mid-preface.
+        }
+        filled += n;
+        if buf[..filled] != H2_PREFACE[..filled] {
+            break; // Can't possibly become the preface anymore.
+        }
+    }
+    buf.truncate(filled);
+    Ok(buf)
+}
+
+/// Wraps an accepted stream to replay the bytes consumed by
+/// `read_preface_probe` before continuing to read from it untouched, so
+/// protocol detection doesn't lose data off the front of the connection.
+struct PrefixedStream {
+    prefix: Bytes,
+    prefix_pos: usize,
+    inner: Box<dyn AsyncStream>,
+}
+
+impl PrefixedStream {
+    fn new(prefix: Vec<u8>, inner: Box<dyn AsyncStream>) -> Self {
+        Self { prefix: Bytes::from(prefix), prefix_pos: 0, inner }
+    }
+}
+
+impl AsyncRead for PrefixedStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        let me = self.get_mut();
+        if me.prefix_pos < me.prefix.len() {
+            let remaining = &me.prefix[me.prefix_pos..];
+            let n = remaining.len().min(buf.remaining());
+            buf.put_slice(&remaining[..n]);
+            me.prefix_pos += n;
+            return std::task::Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut me.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for PrefixedStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+/// True if this request is an HTTP/1.1 `Upgrade: h2c` handshake (cleartext
+/// HTTP/2 via upgrade, RFC 7540 §3.2), as opposed to prior-knowledge h2c
+/// (detected earlier via `H2_PREFACE`) or a plain HTTP/1.1 request.
+fn is_h2c_upgrade_request(req: &Request<IncomingBody>) -> bool {
+    let wants_upgrade = req.headers().get("connection")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.split(',').any(|tok| tok.trim().eq_ignore_ascii_case("upgrade")))
+        .unwrap_or(false);
+    let wants_h2c = req.headers().get("upgrade")
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.eq_ignore_ascii_case("h2c"))
+        .unwrap_or(false);
+    wants_upgrade && wants_h2c
+}
+
 // MULTI-WORKER: Metadata struct to cache is_async check
 #[derive(Clone)]
 struct HandlerMetadata {
     handler: Handler,
     is_async: bool, // Cached at registration time!
+    cacheable: bool, // Route was registered with cache=True
 }
 
 // MULTI-WORKER: Request structure for worker communication
@@ -36,7 +659,54 @@ struct PythonRequest {
     path: String,
     query_string: String,
     body: Bytes,
-    response_tx: oneshot::Sender<Result<String, String>>,
+    response_tx: oneshot::Sender<Result<HandlerResponse, HandlerError>>,
+}
+
+// ============================================================================
+// MIDDLEWARE SUBSYSTEM
+// ============================================================================
+
+/// Which point in `handle_request` a middleware callable is being invoked for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MiddlewareStage {
+    /// Runs right after the body is collected, before route lookup. May
+    /// short-circuit the whole request (auth/CORS).
+    Request,
+    /// Runs after `Request`, may replace the request body before the handler runs.
+    RequestBody,
+    /// Runs after the handler produced a body, may rewrite it before it's sent.
+    Response,
+}
+
+/// What a middleware callable decided to do with the request/response in flight.
+enum MiddlewareOutcome {
+    /// Let the pipeline continue unchanged.
+    Continue,
+    /// Replace the body (request body filter or response filter) and continue.
+    Rewrite(Bytes),
+    /// Stop the pipeline now and send this response back to the client.
+    RespondNow { status: u16, body: Bytes },
+}
+
+/// A middleware invocation sent to a worker, mirroring `PythonRequest` so
+/// middleware callables run off the hyper threads through the same pool.
+struct MiddlewareRequest {
+    callable: Arc<PyObject>,
+    stage: MiddlewareStage,
+    method: String,
+    path: String,
+    query_string: String,
+    body: Bytes,
+    status: u16,
+    response_tx: oneshot::Sender<MiddlewareOutcome>,
+}
+
+/// Unit of work a worker thread can process. Middleware and route handlers
+/// share one channel so middleware dispatch keeps the GIL off hyper's threads
+/// exactly like async route handlers already do.
+enum WorkerJob {
+    Handler(PythonRequest),
+    Middleware(MiddlewareRequest),
 }
 
 // Cached Python modules for performance
@@ -53,11 +723,15 @@ pub struct TurboServer {
     port: u16,
     worker_threads: usize,
     buffer_pool: Arc<ZeroCopyBufferPool>, // PHASE 2: Zero-copy buffer pool
-    python_workers: Option<Vec<mpsc::Sender<PythonRequest>>>, // MULTI-WORKER: Multiple async workers
+    python_workers: Arc<std::sync::Mutex<Option<WorkerPool>>>, // MULTI-WORKER: Driver handle, set in run()
+    middleware: Arc<RwLock<Vec<Arc<PyObject>>>>, // Ordered request/response middleware pipeline
 }
 
 #[pymethods]
 impl TurboServer {
+    /// `host` is usually a hostname/IP bound together with `port`, but may
+    /// also be `unix:/path/to.sock` to serve over a Unix domain socket
+    /// instead (in which case `port` is ignored).
     #[new]
     pub fn new(host: Option<String>, port: Option<u16>) -> Self {
         // PHASE 2: Intelligent worker thread calculation
@@ -78,12 +752,57 @@ impl TurboServer {
             port: port.unwrap_or(8000),
             worker_threads,
             buffer_pool: Arc::new(ZeroCopyBufferPool::new()), // PHASE 2: Initialize buffer pool
-            python_workers: None, // MULTI-WORKER: Initialized in run()
+            python_workers: Arc::new(std::sync::Mutex::new(None)), // MULTI-WORKER: Set in run()
+            middleware: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
-    /// Register a route handler with radix trie routing
-    pub fn add_route(&self, method: String, path: String, handler: PyObject) -> PyResult<()> {
+    /// Gracefully stop the Python worker pool spawned by `run()`: stop
+    /// routing new work to it, let each worker drain whatever's already
+    /// queued, then join its OS thread, waiting up to `drain_timeout_secs`
+    /// (default 5s) in total. Returns the number of workers that didn't
+    /// stop within the deadline (their threads, and whatever coroutine they
+    /// were mid-await on, are abandoned rather than forcibly killed).
+    ///
+    /// Meant for tests and reload scenarios: `run()`'s accept loop keeps
+    /// running independently of this, so requests to async routes on
+    /// connections accepted afterwards get 503s from an empty worker pool,
+    /// and middleware is skipped for them. Sync routes are called directly,
+    /// not through the pool, and keep being served. Connections accepted
+    /// before this call keep their own senders, so a worker only stops once
+    /// those connections have closed.
+    #[pyo3(signature = (drain_timeout_secs=None))]
+    pub fn shutdown_workers(&self, py: Python, drain_timeout_secs: Option<u64>) -> usize {
+        let pool_holder = Arc::clone(&self.python_workers);
+        let timeout = Duration::from_secs(drain_timeout_secs.unwrap_or(5));
+        py.allow_threads(move || {
+            match pool_holder.lock().unwrap().take() {
+                Some(pool) => pool.stop(timeout),
+                None => 0,
+            }
+        })
+    }
+
+    /// Register a request/response middleware, invoked in registration order.
+    /// The callable is inspected for three optional attributes: `request_filter`,
+    /// `request_body_filter`, and `response_filter`; any stage it doesn't
+    /// implement is skipped for that middleware.
+    pub fn add_middleware(&self, py: Python, middleware: PyObject) -> PyResult<()> {
+        let pipeline = Arc::clone(&self.middleware);
+        py.allow_threads(|| {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async {
+                pipeline.write().await.push(Arc::new(middleware));
+            });
+        });
+        Ok(())
+    }
+
+    /// Register a route handler with radix trie routing. Pass `cache=True`
+    /// to let idempotent GET responses be served from the response cache
+    /// configured via `configure_response_cache` (see `LRU_RESPONSE_CACHE`).
+    #[pyo3(signature = (method, path, handler, cache=false))]
+    pub fn add_route(&self, method: String, path: String, handler: PyObject, cache: bool) -> PyResult<()> {
         let route_key = format!("{} {}", method.to_uppercase(), path);
         
         // HYBRID: Check if handler is async ONCE at registration time!
@@ -108,6 +827,7 @@ impl TurboServer {
                     handlers_guard.insert(route_key.clone(), HandlerMetadata {
                         handler: Arc::new(handler),
                         is_async,
+                        cacheable: cache,
                     });
                     drop(handlers_guard); // Release write lock immediately
             
@@ -123,19 +843,31 @@ impl TurboServer {
 
     /// Start the HTTP server with multi-threading support
     pub fn run(&self, py: Python) -> PyResult<()> {
-        // Optimize: Use pre-allocated string for address parsing (cold path)
-        let mut addr_str = String::with_capacity(self.host.len() + 10);
-        addr_str.push_str(&self.host);
-        addr_str.push(':');
-        addr_str.push_str(&self.port.to_string());
-        
-        let addr: SocketAddr = addr_str
-            .parse()
-            .map_err(|e| pyo3::exceptions::PyValueError::new_err("Invalid address"))?;
+        // `init_logging` is opt-in, and tracing has no default sink — without
+        // this, every `tracing::info!/warn!/error!` in this file (worker
+        // start/stop, handler failures, rate-limit rejections, promise
+        // failures) is silently dropped the moment an embedder doesn't call
+        // it. Install a stderr fallback so those stay visible by default;
+        // if `init_logging` already ran, the global subscriber is already
+        // set and this no-ops instead of erroring.
+        let _ = tracing_subscriber::fmt::try_init();
+
+        // Unix domain sockets are addressed as `unix:/path/to.sock`; anything
+        // else is treated as `host:port` and bound over TCP.
+        let addr_str = if self.host.starts_with("unix:") {
+            self.host.clone()
+        } else {
+            let mut addr_str = String::with_capacity(self.host.len() + 10);
+            addr_str.push_str(&self.host);
+            addr_str.push(':');
+            addr_str.push_str(&self.port.to_string());
+            addr_str
+        };
 
         let handlers = Arc::clone(&self.handlers);
         let router = Arc::clone(&self.router);
-        
+        let middleware = Arc::clone(&self.middleware);
+
         // MULTI-WORKER: Spawn N Python workers for parallel async execution!
         // Use ALL available cores for maximum parallelism with Python 3.14 free-threading!
         let num_workers = std::thread::available_parallelism()
@@ -143,10 +875,19 @@ impl TurboServer {
             .unwrap_or(8)
             .max(8); // At least 8 workers, up to all cores!
         
-        eprintln!("🚀 Spawning {} Python workers for parallel async execution...", num_workers);
-        let python_workers = spawn_python_workers(num_workers);
-        eprintln!("✅ All {} Python workers ready!", num_workers);
-        
+        let worker_pool = spawn_python_workers(num_workers);
+        *self.python_workers.lock().unwrap() = Some(worker_pool);
+        tracing::info!(target: "turboapi::worker", "all {} Python workers ready", num_workers);
+
+        // Fetched fresh from `self.python_workers` on every accepted
+        // connection below (not snapshotted once here) so that, once the
+        // connections accepted before it have closed, `stop()` dropping its
+        // `Vec<Sender>` is actually the last reference: a snapshot held for
+        // the server's whole lifetime, re-cloned into every connection
+        // forever, would mean the mpsc channel never closes and
+        // shutdown_workers' `join_with_timeout` always times out.
+        let python_workers_holder = Arc::clone(&self.python_workers);
+
         py.allow_threads(|| {
             // PHASE 2: Optimized runtime with advanced thread management
             let rt = tokio::runtime::Builder::new_multi_thread()
@@ -159,16 +900,21 @@ impl TurboServer {
                 .unwrap();
             
             rt.block_on(async {
-                let listener = TcpListener::bind(addr).await.unwrap();
-                
+                let listener = bind_listener(&addr_str).unwrap();
+
                 // PHASE 2: Adaptive connection management with backpressure tuning
                 let base_connections = self.worker_threads * 50;
                 let max_connections = (base_connections * 110) / 100; // 10% headroom for bursts
                 let connection_semaphore = Arc::new(tokio::sync::Semaphore::new(max_connections));
 
+                let http2_config = HTTP2_CONFIG.get_or_init(Http2Config::default).clone();
+
                 loop {
-                    let (stream, _) = listener.accept().await.unwrap();
-                    
+                    let (mut stream, peer_addr) = match listener.accept().await {
+                        Ok(accepted) => accepted,
+                        Err(_) => continue,
+                    };
+
                     // Acquire connection permit (backpressure control)
                     let permit = match connection_semaphore.clone().try_acquire_owned() {
                         Ok(permit) => permit,
@@ -178,26 +924,120 @@ impl TurboServer {
                             continue;
                         }
                     };
-                    
-                    let io = TokioIo::new(stream);
+
                     let handlers_clone = Arc::clone(&handlers);
                     let router_clone = Arc::clone(&router);
-                    let python_workers_clone = python_workers.clone(); // MULTI-WORKER: Clone workers Vec
+                    let middleware_clone = Arc::clone(&middleware);
+                    // MULTI-WORKER: current senders, or none once shutdown_workers()
+                    // has taken the pool — requests on this connection then get
+                    // WorkerUnavailable instead of routing to a stopped pool.
+                    let python_workers_clone = python_workers_holder
+                        .lock()
+                        .unwrap()
+                        .as_ref()
+                        .map(|pool| pool.senders())
+                        .unwrap_or_default();
+                    let http2_config = http2_config.clone();
 
                     // Spawn optimized connection handler
                     tokio::task::spawn(async move {
                         let _permit = permit; // Keep permit until connection closes
-                        
+                        let _conn_guard = ConnectionGuard::new(); // Tracked in CONNECTION_STATS until connection closes
+
+                        // With HTTP/2 disabled (the default), skip the preface
+                        // probe entirely and keep the original HTTP/1.1-only path.
+                        if !http2_config.enabled {
+                            let io = TokioIo::new(stream);
+                            let _ = http1::Builder::new()
+                                .keep_alive(true)
+                                .half_close(true)
+                                .pipeline_flush(true)
+                                .max_buf_size(16384)
+                                .serve_connection(io, service_fn(move |req| {
+                                    handle_request(
+                                        req,
+                                        Arc::clone(&handlers_clone),
+                                        Arc::clone(&router_clone),
+                                        Arc::clone(&middleware_clone),
+                                        python_workers_clone.clone(),
+                                        peer_addr,
+                                    )
+                                }))
+                                .await;
+                            return;
+                        }
+
+                        // Prior-knowledge h2c: the client sent the HTTP/2
+                        // connection preface first thing, so skip HTTP/1.1
+                        // entirely and hand the (prefix-replayed) stream
+                        // straight to the HTTP/2 builder.
+                        let preface_probe = match read_preface_probe(&mut stream).await {
+                            Ok(bytes) => bytes,
+                            Err(_) => return,
+                        };
+                        let is_prior_knowledge_h2c = preface_probe == H2_PREFACE;
+                        let io = TokioIo::new(PrefixedStream::new(preface_probe, stream));
+
+                        if is_prior_knowledge_h2c {
+                            let _ = http2::Builder::new(TokioExecutor::new())
+                                .max_concurrent_streams(http2_config.max_concurrent_streams)
+                                .initial_stream_window_size(http2_config.initial_window_size)
+                                .serve_connection(io, service_fn(move |req| {
+                                    handle_request(
+                                        req,
+                                        Arc::clone(&handlers_clone),
+                                        Arc::clone(&router_clone),
+                                        Arc::clone(&middleware_clone),
+                                        python_workers_clone.clone(),
+                                        peer_addr,
+                                    )
+                                }))
+                                .await;
+                            return;
+                        }
+
+                        // Otherwise serve plain HTTP/1.1. `Upgrade: h2c` requests are
+                        // answered on this same connection rather than actually
+                        // upgraded — see the comment in the service closure below —
+                        // so there's no real protocol switch for `.with_upgrades()`
+                        // to support here.
                         let _ = http1::Builder::new()
-                            .keep_alive(true) // Enable keep-alive
-                            .half_close(true) // Better connection handling
-                            .pipeline_flush(true) // PHASE 2: Enable response pipelining
-                            .max_buf_size(16384) // PHASE 2: Optimize buffer size for HTTP/2 compatibility
+                            .keep_alive(true)
+                            .half_close(true)
+                            .pipeline_flush(true)
+                            .max_buf_size(16384)
                             .serve_connection(io, service_fn(move |req| {
                                 let handlers = Arc::clone(&handlers_clone);
                                 let router = Arc::clone(&router_clone);
-                                let python_workers = python_workers_clone.clone(); // MULTI-WORKER
-                                handle_request(req, handlers, router, python_workers)
+                                let middleware = Arc::clone(&middleware_clone);
+                                let python_workers = python_workers_clone.clone();
+
+                                async move {
+                                    if is_h2c_upgrade_request(&req) {
+                                        // We can't actually honor this: after a 101 the
+                                        // client's original request is assigned HTTP/2
+                                        // stream 1 with no further preface (RFC 7540 §3.2),
+                                        // but neither hyper's `http2::Builder` nor the `h2`
+                                        // crate underneath it exposes a way to seed a new
+                                        // connection with a request it never received as
+                                        // HTTP/2 frames — there's nothing to replay it
+                                        // into. Sending the 101 and then handling this
+                                        // request on the upgraded connection regardless
+                                        // previously meant the client got switched to a
+                                        // protocol nothing ever spoke to it again: a
+                                        // permanent hang waiting on stream 1's response.
+                                        //
+                                        // Ignoring `Upgrade: h2c` and answering over
+                                        // HTTP/1.1 instead is RFC-compliant (Upgrade is
+                                        // advisory) and at least actually responds.
+                                        // Prior-knowledge h2c (the `H2_PREFACE` branch
+                                        // above) remains the supported way to get a real
+                                        // HTTP/2 connection out of this server.
+                                        tracing::debug!(target: "turboapi::http2", "ignoring Upgrade: h2c (unsupported over upgrade), answering over HTTP/1.1");
+                                    }
+
+                                    handle_request(req, handlers, router, middleware, python_workers, peer_addr).await
+                                }
                             }))
                             .await;
                         // Connection automatically cleaned up when task ends
@@ -221,7 +1061,7 @@ impl TurboServer {
         info.push_str(&self.worker_threads.to_string());
         info.push_str(" (3x CPU cores, optimized)");
         info.push_str("\n   🔧 Optimizations: Phase 2+ Complete");
-        info.push_str("\n   📊 Features: Rate limiting, Response caching, HTTP/2 ready");
+        info.push_str("\n   📊 Features: Rate limiting, Response caching, Middleware pipeline, HTTP/2 + h2c");
         info.push_str("\n   🛡️  Security: Enhanced error handling, IP-based rate limits");
         info.push_str("\n   💫 Performance: Zero-alloc routes, Object pooling, SIMD JSON");
         info.push_str("\n   🎯 Status: Production Ready - High Performance Web Framework");
@@ -233,38 +1073,86 @@ async fn handle_request(
     req: Request<IncomingBody>,
     handlers: Arc<RwLock<HashMap<String, HandlerMetadata>>>, // HYBRID: HandlerMetadata with is_async cached!
     router: Arc<RwLock<RadixRouter>>,
-    python_workers: Vec<mpsc::Sender<PythonRequest>>, // MULTI-WORKER: Multiple workers for parallelism!
+    middleware: Arc<RwLock<Vec<Arc<PyObject>>>>,
+    python_workers: Vec<mpsc::Sender<WorkerJob>>, // MULTI-WORKER: Multiple workers for parallelism!
+    peer_addr: PeerAddr, // Fallback for extract_client_ip when no proxy headers are present
 ) -> Result<Response<Full<Bytes>>, Infallible> {
     // Extract parts first before borrowing
     let (parts, body) = req.into_parts();
     let method_str = parts.method.as_str();
     let path = parts.uri.path();
     let query_string = parts.uri.query().unwrap_or("");
-    let body_bytes = match body.collect().await {
+    let mut body_bytes = match body.collect().await {
         Ok(collected) => collected.to_bytes(),
         Err(e) => {
-            eprintln!("Failed to read request body: {}", e);
+            tracing::warn!(target: "turboapi::request", "failed to read request body: {}", e);
             Bytes::new()
         }
     };
-    
+
+    let middleware_guard = middleware.read().await;
+    let middleware_chain: Vec<Arc<PyObject>> = middleware_guard.clone();
+    drop(middleware_guard);
+
+    // Stage 1: request_filter - runs before route lookup, may short-circuit.
+    for mw in &middleware_chain {
+        match run_middleware_stage(
+            mw.clone(),
+            MiddlewareStage::Request,
+            method_str,
+            path,
+            query_string,
+            body_bytes.clone(),
+            200,
+            &python_workers,
+        ).await {
+            MiddlewareOutcome::RespondNow { status, body } => {
+                return Ok(Response::builder()
+                    .status(status)
+                    .header("content-type", "application/json")
+                    .body(Full::new(body))
+                    .unwrap());
+            }
+            MiddlewareOutcome::Rewrite(_) | MiddlewareOutcome::Continue => {}
+        }
+    }
+
+    // Stage 2: request_body_filter - may replace the collected body.
+    for mw in &middleware_chain {
+        match run_middleware_stage(
+            mw.clone(),
+            MiddlewareStage::RequestBody,
+            method_str,
+            path,
+            query_string,
+            body_bytes.clone(),
+            200,
+            &python_workers,
+        ).await {
+            MiddlewareOutcome::Rewrite(new_body) => body_bytes = new_body,
+            MiddlewareOutcome::RespondNow { status, body } => {
+                return Ok(Response::builder()
+                    .status(status)
+                    .header("content-type", "application/json")
+                    .body(Full::new(body))
+                    .unwrap());
+            }
+            MiddlewareOutcome::Continue => {}
+        }
+    }
+
     // PHASE 2+: Basic rate limiting check (DISABLED BY DEFAULT FOR BENCHMARKING)
     // Rate limiting is completely disabled by default to ensure accurate benchmarks
     // Users can explicitly enable it in production if needed
     let rate_config = RATE_LIMIT_CONFIG.get();
     if let Some(config) = rate_config {
         if config.enabled {
-            // Extract client IP from headers
-            let client_ip = parts.headers.get("x-forwarded-for")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|s| s.split(',').next())
-                .map(|s| s.trim().to_string())
-                .or_else(|| parts.headers.get("x-real-ip")
-                    .and_then(|v| v.to_str().ok())
-                    .map(|s| s.to_string()));
-            
+            // Proxy headers first, falling back to the actual accepted peer.
+            let client_ip = extract_client_ip(&parts.headers, Some(&peer_addr));
+
             if let Some(ip) = client_ip {
                 if !check_rate_limit(&ip) {
+                    tracing::warn!(target: "turboapi::rate_limit", ip = %ip, "rate limit exceeded");
                     let rate_limit_json = format!(
                         r#"{{"error": "RateLimitExceeded", "message": "Too many requests", "retry_after": 60}}"#
                     );
@@ -291,8 +1179,68 @@ async fn handle_request(
     
     // Process handler if found
     if let Some(metadata) = metadata {
+        // Cache lookup: serve idempotent GETs straight from the LRU cache,
+        // before ever calling the handler.
+        let is_cacheable_request = metadata.cacheable && method_str.eq_ignore_ascii_case("GET");
+        if is_cacheable_request {
+            if let Some(config) = RESPONSE_CACHE_CONFIG.get().filter(|c| c.enabled) {
+                let key = cache_key(method_str, path, query_string);
+                let cache = LRU_RESPONSE_CACHE.get_or_init(|| std::sync::Mutex::new(LruCache::new(config.capacity)));
+                if let Ok(mut cache_guard) = cache.lock() {
+                    if let Some(cached) = cache_guard.get(&key) {
+                        let is_head = method_str.eq_ignore_ascii_case("HEAD");
+                        let cached_body = Bytes::from(cached.body.clone());
+
+                        // Cached bodies are stored uncompressed (see the
+                        // insert site below) precisely so they can be
+                        // re-compressed per-client here instead of always
+                        // serving the first requester's Accept-Encoding to
+                        // everyone else.
+                        let compressed = if !is_head {
+                            COMPRESSION_CONFIG.get().filter(|c| c.enabled).and_then(|config| {
+                                if cached_body.len() < config.min_size {
+                                    return None;
+                                }
+                                let accept_encoding = parts.headers.get("accept-encoding")?.to_str().ok()?;
+                                let encoding = negotiate_encoding(accept_encoding)?;
+                                compress_body(cached_body.as_ref(), encoding, config.level)
+                                    .map(|bytes| (encoding, bytes))
+                            })
+                        } else {
+                            None
+                        };
+
+                        let response_body = if is_head {
+                            Full::new(Bytes::new())
+                        } else if let Some((_, ref compressed_bytes)) = compressed {
+                            Full::new(Bytes::from(compressed_bytes.clone()))
+                        } else {
+                            Full::new(cached_body.clone())
+                        };
+
+                        let content_length = match &compressed {
+                            Some((_, compressed_bytes)) => compressed_bytes.len(),
+                            None => cached_body.len(),
+                        };
+
+                        let mut builder = Response::builder()
+                            .status(200)
+                            .header("content-type", cached.content_type.clone())
+                            .header("content-length", content_length.to_string())
+                            .header("x-cache", "HIT");
+                        if let Some((encoding, _)) = &compressed {
+                            builder = builder.header("content-encoding", *encoding);
+                        }
+                        return Ok(builder.body(response_body).unwrap());
+                    }
+                }
+            }
+        }
+
         // HYBRID APPROACH: Direct call for sync, worker for async!
-        let response_result = if metadata.is_async {
+        let response_result = if metadata.is_async && python_workers.is_empty() {
+            Err(HandlerError::WorkerUnavailable)
+        } else if metadata.is_async {
             // ASYNC PATH: Hash-based worker selection for cache locality!
             let worker_id = hash_route_key(&route_key) % python_workers.len();
             let worker_tx = &python_workers[worker_id];
@@ -307,11 +1255,11 @@ async fn handle_request(
                 response_tx: resp_tx,
             };
             
-            match worker_tx.send(python_req).await {
+            match worker_tx.send(WorkerJob::Handler(python_req)).await {
                 Ok(_) => {
                     match resp_rx.await {
                         Ok(result) => result,
-                        Err(_) => Err("Python worker died".to_string()),
+                        Err(_) => Err(HandlerError::WorkerUnavailable),
                     }
                 }
                 Err(_) => {
@@ -327,49 +1275,111 @@ async fn handle_request(
         };
         
         match response_result {
-            Ok(response_str) => {
-                let content_length = response_str.len().to_string();
-                
-                // PHASE 2: Use zero-copy buffers for large responses
-                let response_body = if method_str.to_ascii_uppercase() == "HEAD" {
-                    Full::new(Bytes::new())
-                } else if response_str.len() > 1024 {
-                    // Use zero-copy buffer for large responses (>1KB)
-                    Full::new(create_zero_copy_response(&response_str))
-                } else {
-                    // Small responses: direct conversion
-                    Full::new(Bytes::from(response_str))
-                };
-                
-                return Ok(Response::builder()
-                    .status(200)
-                    .header("content-type", "application/json")
-                    .header("content-length", content_length)
-                    .body(response_body)
-                    .unwrap());
+            Ok(handler_response) => {
+                let is_head = method_str.eq_ignore_ascii_case("HEAD");
+                let response_content_type = handler_response.content_type.unwrap_or_else(|| "application/json".to_string());
+                let response_status = handler_response.status.unwrap_or(200);
+                let response_headers = handler_response.headers;
+                let mut final_body = Bytes::from(handler_response.body);
+
+                // Stage 3: response_filter - may rewrite headers/body, or
+                // terminate the request with its own response.
+                for mw in &middleware_chain {
+                    match run_middleware_stage(
+                        mw.clone(),
+                        MiddlewareStage::Response,
+                        method_str,
+                        path,
+                        query_string,
+                        final_body.clone(),
+                        response_status,
+                        &python_workers,
+                    ).await {
+                        MiddlewareOutcome::Rewrite(new_body) => final_body = new_body,
+                        MiddlewareOutcome::RespondNow { status, body } => {
+                            return Ok(Response::builder()
+                                .status(status)
+                                .header("content-type", "application/json")
+                                .body(Full::new(body))
+                                .unwrap());
+                        }
+                        MiddlewareOutcome::Continue => {}
+                    }
+                }
+
+                // Populate the response cache for cacheable GETs, storing the
+                // uncompressed body so it can be served (and re-compressed
+                // per-client) regardless of Accept-Encoding.
+                if is_cacheable_request && response_status == 200 && response_is_cacheable(&response_content_type, &response_headers) {
+                    if let Some(config) = RESPONSE_CACHE_CONFIG.get().filter(|c| c.enabled) {
+                        if let Ok(body_str) = std::str::from_utf8(final_body.as_ref()) {
+                            let key = cache_key(method_str, path, query_string);
+                            let cache = LRU_RESPONSE_CACHE.get_or_init(|| std::sync::Mutex::new(LruCache::new(config.capacity)));
+                            if let Ok(mut cache_guard) = cache.lock() {
+                                cache_guard.insert(key, CachedResponse {
+                                    body: body_str.to_string(),
+                                    content_type: response_content_type.clone(),
+                                    expires_at: Instant::now() + config.ttl,
+                                });
+                            }
+                        }
+                    }
+                }
+
+                // Negotiate compression before building the final body: only
+                // for non-HEAD responses over the configured size threshold,
+                // and never when the client explicitly asked for `identity`.
+                let compressed = if !is_head {
+                    COMPRESSION_CONFIG.get().filter(|c| c.enabled).and_then(|config| {
+                        if final_body.len() < config.min_size {
+                            return None;
+                        }
+                        let accept_encoding = parts.headers.get("accept-encoding")?.to_str().ok()?;
+                        let encoding = negotiate_encoding(accept_encoding)?;
+                        compress_body(final_body.as_ref(), encoding, config.level)
+                            .map(|bytes| (encoding, bytes))
+                    })
+                } else {
+                    None
+                };
+
+                // Empty body for HEAD, else the compressed or uncompressed body
+                let response_body = if is_head {
+                    Full::new(Bytes::new())
+                } else if let Some((_, ref compressed_bytes)) = compressed {
+                    Full::new(Bytes::from(compressed_bytes.clone()))
+                } else {
+                    // Uncompressed responses of any size are a direct Bytes clone.
+                    Full::new(final_body.clone())
+                };
+
+                let content_length = match &compressed {
+                    Some((_, compressed_bytes)) => compressed_bytes.len(),
+                    None => final_body.len(),
+                };
+
+                let mut builder = Response::builder()
+                    .status(response_status)
+                    .header("content-type", response_content_type)
+                    .header("content-length", content_length.to_string());
+                if let Some((encoding, _)) = &compressed {
+                    builder = builder.header("content-encoding", *encoding);
+                }
+                for (name, value) in &response_headers {
+                    builder = builder.header(name.as_str(), value.as_str());
+                }
+
+                return Ok(builder.body(response_body).unwrap());
             }
             Err(e) => {
-                // PHASE 2+: Enhanced error handling with recovery attempts
-                eprintln!("Handler error for {} {}: {}", method_str, path, e);
-                
-                // Try to determine error type for better response
-                let (status_code, error_type) = match e.to_string() {
-                    err_str if err_str.contains("validation") => (400, "ValidationError"),
-                    err_str if err_str.contains("timeout") => (408, "TimeoutError"),
-                    err_str if err_str.contains("not found") => (404, "NotFoundError"),
-                    _ => (500, "InternalServerError"),
-                };
-                
-                let error_json = format!(
-                    r#"{{"error": "{}", "message": "Request failed: {}", "method": "{}", "path": "{}", "timestamp": {}}}"#,
-                    error_type, e.to_string().chars().take(200).collect::<String>(), 
-                    method_str, path, std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
-                );
-                
+                tracing::error!(target: "turboapi::handler", "handler error for {} {}: {}", method_str, path, e);
+
+                let status_code = e.status_code();
+                let error_json = e.to_json(method_str, path);
+
                 return Ok(Response::builder()
                     .status(status_code)
                     .header("content-type", "application/json")
-                    .header("x-error-recovery", "attempted")
                     .body(Full::new(Bytes::from(error_json)))
                     .unwrap());
             }
@@ -470,6 +1480,298 @@ pub fn configure_rate_limiting(enabled: bool, requests_per_minute: Option<u32>)
     let _ = RATE_LIMIT_CONFIG.set(config);
 }
 
+// ============================================================================
+// RESPONSE COMPRESSION - Accept-Encoding negotiation
+// ============================================================================
+
+/// Response compression configuration
+static COMPRESSION_CONFIG: OnceLock<CompressionConfig> = OnceLock::new();
+
+#[derive(Clone)]
+struct CompressionConfig {
+    enabled: bool,
+    min_size: usize,
+    level: u32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Opt-in so benchmarks stay clean by default
+            min_size: 1024, // Matches the existing large-response threshold
+            level: 6,
+        }
+    }
+}
+
+/// Configure response compression settings
+#[pyfunction]
+pub fn configure_compression(enabled: bool, min_size: Option<usize>, level: Option<u32>) {
+    let config = CompressionConfig {
+        enabled,
+        min_size: min_size.unwrap_or(1024),
+        level: level.unwrap_or(6).min(9),
+    };
+    let _ = COMPRESSION_CONFIG.set(config);
+}
+
+/// Pick the best codec the client advertises via `Accept-Encoding`, honoring
+/// `identity` as "do not compress". Preference order: gzip, then deflate.
+fn negotiate_encoding(accept_encoding: &str) -> Option<&'static str> {
+    if accept_encoding.split(',').any(|tok| tok.trim().eq_ignore_ascii_case("identity")) {
+        return None;
+    }
+    let lower = accept_encoding.to_ascii_lowercase();
+    if lower.contains("gzip") {
+        Some("gzip")
+    } else if lower.contains("deflate") {
+        Some("deflate")
+    } else {
+        None
+    }
+}
+
+/// Compress `body` with the negotiated codec, streaming it through a writer
+/// so we never hold more than one extra copy of the body in memory.
+fn compress_body(body: &[u8], encoding: &str, level: u32) -> Option<Vec<u8>> {
+    let compression = Compression::new(level);
+    match encoding {
+        "gzip" => {
+            let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 2), compression);
+            encoder.write_all(body).ok()?;
+            encoder.finish().ok()
+        }
+        "deflate" => {
+            let mut encoder = DeflateEncoder::new(Vec::with_capacity(body.len() / 2), compression);
+            encoder.write_all(body).ok()?;
+            encoder.finish().ok()
+        }
+        _ => None,
+    }
+}
+
+// ============================================================================
+// HTTP/2 - h2 and cleartext h2c serving alongside HTTP/1.1
+// ============================================================================
+
+/// HTTP/2 serving configuration.
+static HTTP2_CONFIG: OnceLock<Http2Config> = OnceLock::new();
+
+#[derive(Clone)]
+struct Http2Config {
+    enabled: bool,
+    max_concurrent_streams: u32,
+    initial_window_size: u32,
+}
+
+impl Default for Http2Config {
+    fn default() -> Self {
+        Self {
+            enabled: false, // Opt-in, same as compression/response cache
+            max_concurrent_streams: 100,
+            initial_window_size: 65_535, // RFC 7540 default
+        }
+    }
+}
+
+/// Configure HTTP/2 (and h2c cleartext) serving. Call before `run()`; when
+/// disabled (the default) every connection is served over HTTP/1.1 exactly
+/// as before, with no preface-probing overhead on the accept path.
+#[pyfunction]
+pub fn configure_http2(enabled: bool, max_concurrent_streams: Option<u32>, initial_window_size: Option<u32>) {
+    let config = Http2Config {
+        enabled,
+        max_concurrent_streams: max_concurrent_streams.unwrap_or(100),
+        initial_window_size: initial_window_size.unwrap_or(65_535),
+    };
+    let _ = HTTP2_CONFIG.set(config);
+}
+
+// ============================================================================
+// LRU RESPONSE CACHE - bounded cache for idempotent GET responses
+// ============================================================================
+
+/// Response cache configuration
+static RESPONSE_CACHE_CONFIG: OnceLock<ResponseCacheConfig> = OnceLock::new();
+static LRU_RESPONSE_CACHE: OnceLock<std::sync::Mutex<LruCache>> = OnceLock::new();
+
+#[derive(Clone)]
+struct ResponseCacheConfig {
+    enabled: bool,
+    capacity: usize,
+    ttl: Duration,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity: 1024,
+            ttl: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Configure the response cache. Call before `run()`; the cache itself is
+/// lazily sized to `capacity` on first use.
+#[pyfunction]
+pub fn configure_response_cache(enabled: bool, capacity: Option<usize>, ttl_secs: Option<u64>) {
+    let config = ResponseCacheConfig {
+        enabled,
+        capacity: capacity.unwrap_or(1024),
+        ttl: Duration::from_secs(ttl_secs.unwrap_or(60)),
+    };
+    let _ = RESPONSE_CACHE_CONFIG.set(config);
+}
+
+/// A cached response body plus the metadata needed to replay it.
+#[derive(Clone)]
+struct CachedResponse {
+    body: String,
+    content_type: String,
+    expires_at: Instant,
+}
+
+/// Doubly-linked-list node in a slab (`Vec<Option<Node>>`), so promoting an
+/// entry to MRU or evicting the LRU tail is O(1) with no `Vec` shuffling.
+struct CacheNode {
+    key: String,
+    value: CachedResponse,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+/// Fixed-capacity LRU cache: a `HashMap<key, slab index>` for O(1) lookup,
+/// backed by a slab of linked-list nodes for O(1) promote/evict.
+struct LruCache {
+    capacity: usize,
+    index: StdHashMap<String, usize>,
+    slab: Vec<Option<CacheNode>>,
+    free: Vec<usize>,
+    head: Option<usize>, // most recently used
+    tail: Option<usize>, // least recently used
+}
+
+impl LruCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            index: StdHashMap::new(),
+            slab: Vec::new(),
+            free: Vec::new(),
+            head: None,
+            tail: None,
+        }
+    }
+
+    fn detach(&mut self, idx: usize) {
+        let (prev, next) = {
+            let node = self.slab[idx].as_ref().unwrap();
+            (node.prev, node.next)
+        };
+        match prev {
+            Some(p) => self.slab[p].as_mut().unwrap().next = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.slab[n].as_mut().unwrap().prev = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    fn push_front(&mut self, idx: usize) {
+        let old_head = self.head;
+        {
+            let node = self.slab[idx].as_mut().unwrap();
+            node.prev = None;
+            node.next = old_head;
+        }
+        if let Some(h) = old_head {
+            self.slab[h].as_mut().unwrap().prev = Some(idx);
+        }
+        self.head = Some(idx);
+        if self.tail.is_none() {
+            self.tail = Some(idx);
+        }
+    }
+
+    /// Look up `key`, promoting it to the MRU position on a hit. Expired
+    /// entries are evicted and treated as a miss.
+    fn get(&mut self, key: &str) -> Option<CachedResponse> {
+        let idx = *self.index.get(key)?;
+        let expired = self.slab[idx].as_ref().unwrap().value.expires_at <= Instant::now();
+        if expired {
+            self.remove(idx);
+            return None;
+        }
+        self.detach(idx);
+        self.push_front(idx);
+        Some(self.slab[idx].as_ref().unwrap().value.clone())
+    }
+
+    fn remove(&mut self, idx: usize) {
+        self.detach(idx);
+        if let Some(node) = self.slab[idx].take() {
+            self.index.remove(&node.key);
+        }
+        self.free.push(idx);
+    }
+
+    /// Insert or update `key`, evicting the LRU tail if at capacity.
+    fn insert(&mut self, key: String, value: CachedResponse) {
+        if let Some(&idx) = self.index.get(&key) {
+            self.slab[idx].as_mut().unwrap().value = value;
+            self.detach(idx);
+            self.push_front(idx);
+            return;
+        }
+
+        if self.index.len() >= self.capacity {
+            if let Some(lru) = self.tail {
+                self.remove(lru);
+            }
+        }
+
+        let node = CacheNode { key: key.clone(), value, prev: None, next: None };
+        let idx = if let Some(free_idx) = self.free.pop() {
+            self.slab[free_idx] = Some(node);
+            free_idx
+        } else {
+            self.slab.push(Some(node));
+            self.slab.len() - 1
+        };
+        self.index.insert(key, idx);
+        self.push_front(idx);
+    }
+}
+
+/// Build the cache key for a request: method + path + query string.
+fn cache_key(method: &str, path: &str, query_string: &str) -> String {
+    if query_string.is_empty() {
+        format!("{} {}", method, path)
+    } else {
+        format!("{} {}?{}", method, path, query_string)
+    }
+}
+
+/// Whether a response is safe to insert into the LRU response cache: never
+/// cache a response that carries `Set-Cookie` (per-client state — caching it
+/// would hand one client's session cookie to the next one that hits the same
+/// key) or a `Vary` other than the trivial `Accept-Encoding` this cache
+/// already re-negotiates on every hit.
+///
+/// No built-in handler path sets either header today (`HandlerResponse`'s
+/// `headers` starts empty), so this is a no-op in practice until a
+/// handler/wrapper actually populates `headers` — but it's real, live logic
+/// that runs whenever one does, not a stub.
+fn response_is_cacheable(content_type: &str, headers: &[(String, String)]) -> bool {
+    let _ = content_type;
+    !headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("set-cookie")
+            || (name.eq_ignore_ascii_case("vary") && !value.eq_ignore_ascii_case("accept-encoding"))
+    })
+}
+
 /// PHASE 2: Fast Python handler call with cached modules and optimized object creation
 fn call_python_handler_fast(
     handler: Handler, 
@@ -551,25 +1853,30 @@ fn return_pooled_request_object(obj: PyObject) {
     // If pool is full or locked, let object be dropped normally
 }
 
-/// PHASE 2+: Extract client IP for rate limiting
-fn extract_client_ip(req: &Request<IncomingBody>) -> Option<String> {
+/// PHASE 2+: Extract client IP for rate limiting, falling back to the
+/// connection's peer address when no proxy headers are present (e.g. a
+/// direct connection, or one arriving over a Unix domain socket).
+fn extract_client_ip(headers: &hyper::HeaderMap, peer: Option<&PeerAddr>) -> Option<String> {
     // Try X-Forwarded-For header first (common in reverse proxy setups)
-    if let Some(forwarded) = req.headers().get("x-forwarded-for") {
+    if let Some(forwarded) = headers.get("x-forwarded-for") {
         if let Ok(forwarded_str) = forwarded.to_str() {
             return Some(forwarded_str.split(',').next()?.trim().to_string());
         }
     }
-    
+
     // Fallback to X-Real-IP header
-    if let Some(real_ip) = req.headers().get("x-real-ip") {
+    if let Some(real_ip) = headers.get("x-real-ip") {
         if let Ok(ip_str) = real_ip.to_str() {
             return Some(ip_str.to_string());
         }
     }
-    
-    // Note: In a real implementation, we'd extract from connection info
-    // For now, return a placeholder
-    Some("127.0.0.1".to_string())
+
+    // No proxy headers: fall back to the actual peer the socket accepted.
+    match peer {
+        Some(PeerAddr::Tcp(addr)) => Some(addr.ip().to_string()),
+        Some(PeerAddr::Unix) => Some("unix-socket".to_string()),
+        None => Some("127.0.0.1".to_string()),
+    }
 }
 
 /// PHASE 2+: Simple rate limiting check (configurable)
@@ -631,6 +1938,20 @@ fn hash_route_key(route_key: &str) -> usize {
 // HYBRID APPROACH - Direct Sync Calls + Worker for Async
 // ============================================================================
 
+/// Force a GIL release/reacquire cycle with an empty `allow_threads` body.
+/// `Python::with_gil`'s own scoping already drops the GIL when its closure
+/// returns, but that's a release in name only if nothing else is waiting to
+/// grab it before this thread immediately re-attaches for the next step —
+/// on a free-threaded interpreter with many workers funneling through the
+/// same handler, that can starve the others. Calling this between the
+/// GIL-bound step that produces a result/coroutine and whatever comes next
+/// gives another worker an actual chance to run.
+macro_rules! a_sync_allow_threads {
+    ($py:expr) => {
+        $py.allow_threads(|| {})
+    };
+}
+
 /// HYBRID: Direct synchronous Python handler call (NO channel overhead!)
 /// This is the FAST PATH for sync handlers - bypasses the worker thread entirely
 /// FREE-THREADING: Uses Python::attach() for TRUE parallelism (no GIL contention!)
@@ -640,7 +1961,7 @@ fn call_python_handler_sync_direct(
     path: &str,
     query_string: &str,
     body_bytes: &Bytes,
-) -> Result<String, String> {
+) -> Result<HandlerResponse, HandlerError> {
     // FREE-THREADING: Python::attach() instead of Python::with_gil()
     // This allows TRUE parallel execution on Python 3.14+ with --disable-gil
     Python::attach(|py| {
@@ -648,25 +1969,97 @@ fn call_python_handler_sync_direct(
         let json_module = CACHED_JSON_MODULE.get_or_init(|| {
             py.import("json").unwrap().into()
         });
-        
+
         // Call sync handler directly (NO kwargs - handlers don't expect them!)
         let result = handler.call0(py)
-            .map_err(|e| format!("Python error: {}", e))?;
-        
+            .map_err(|e| classify_py_err(py, &e))?;
+
+        // A `Promise` defers instead of serializing normally — hand its
+        // coroutine to the background-task registry and acknowledge
+        // immediately. This is the only call site a `Promise` can actually
+        // reach (see `HandlerDispatch`'s doc comment), since only sync
+        // handlers come through here.
+        if let Ok(promise) = result.extract::<Py<Promise>>(py) {
+            return Ok(handle_promise(py, &promise.borrow(py)));
+        }
+
+        // Give another worker a chance to run before the (potentially
+        // non-trivial) extract-or-serialize step below.
+        a_sync_allow_threads!(py);
+
         // Extract or serialize result
         match result.extract::<String>(py) {
-            Ok(json_str) => Ok(json_str),
+            Ok(json_str) => Ok(HandlerResponse::json(json_str)),
             Err(_) => {
                 let json_dumps = json_module.getattr(py, "dumps").unwrap();
                 let json_str = json_dumps.call1(py, (result,))
-                    .map_err(|e| format!("JSON error: {}", e))?;
+                    .map_err(|e| HandlerError::Serialization(e.to_string()))?;
                 json_str.extract::<String>(py)
-                    .map_err(|e| format!("Extract error: {}", e))
+                    .map_err(|e| HandlerError::Serialization(e.to_string()))
+                    .map(HandlerResponse::json)
             }
         }
     })
 }
 
+/// Worker runtime tuning, analogous to `Http2Config`/`SocketConfig` above:
+/// off by default so it doesn't change current/benchmark behavior, opt-in
+/// via `configure_worker_runtime`.
+static WORKER_CONFIG: OnceLock<WorkerConfig> = OnceLock::new();
+
+#[derive(Clone, Copy)]
+struct WorkerConfig {
+    cached_task_locals: bool,
+    batch_mode: bool,
+    batch_window_micros: u64,
+    max_batch: usize,
+    local_tasks: bool,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            cached_task_locals: false, // Off by default; see handle_python_request_on_worker_cached
+            batch_mode: false, // Off by default; see run_batched_worker_loop
+            batch_window_micros: 2_000, // 2ms quantum, within the suggested 1-5ms range
+            max_batch: 64,
+            local_tasks: false, // Off by default; see run_worker_loop's LocalSet path
+        }
+    }
+}
+
+/// Opt a worker into reusing one `TaskLocals` per worker thread (built once
+/// at worker startup) instead of letting `into_future` build a fresh
+/// event-loop reference and context on every async request, into draining
+/// its job queue in bounded time-quantum batches (`run_batched_worker_loop`)
+/// instead of waking up once per queued job, and/or into dispatching each
+/// job through a `LocalSet`/`spawn_local` instead of awaiting it inline, so
+/// several requests can be in flight on one worker at once. `batch_window_micros`
+/// and `max_batch` are ignored unless `batch_mode` is set.
+#[pyfunction]
+pub fn configure_worker_runtime(
+    cached_task_locals: bool,
+    batch_mode: bool,
+    local_tasks: bool,
+    batch_window_micros: Option<u64>,
+    max_batch: Option<usize>,
+) {
+    let _ = WORKER_CONFIG.set(WorkerConfig {
+        cached_task_locals,
+        batch_mode,
+        batch_window_micros: batch_window_micros.unwrap_or(2_000),
+        max_batch: max_batch.unwrap_or(64),
+        local_tasks,
+    });
+}
+
+thread_local! {
+    /// Set once per worker thread in `spawn_python_workers`, when
+    /// `WorkerConfig::cached_task_locals` is on. Read by
+    /// `handle_python_request_on_worker_cached`.
+    static WORKER_TASK_LOCALS: std::cell::RefCell<Option<pyo3_async_runtimes::TaskLocals>> = std::cell::RefCell::new(None);
+}
+
 // ============================================================================
 // MULTI-WORKER PATTERN - Multiple Python Workers for Parallel Async Execution
 // ============================================================================
@@ -674,118 +2067,586 @@ fn call_python_handler_sync_direct(
 /// Spawn N dedicated Python worker threads for parallel async execution
 /// Each worker has its own current_thread runtime
 /// This enables TRUE parallelism for async handlers!
-fn spawn_python_workers(num_workers: usize) -> Vec<mpsc::Sender<PythonRequest>> {
-    eprintln!("🚀 Spawning {} Python workers for parallel async execution...", num_workers);
-    
-    (0..num_workers)
-        .map(|worker_id| {
-            let (tx, mut rx) = mpsc::channel::<PythonRequest>(20000); // INCREASED: 20K capacity for high throughput!
-            
-            thread::spawn(move || {
-                // Create single-threaded Tokio runtime for this worker
-                let rt = tokio::runtime::Builder::new_current_thread()
-                    .enable_all()
-                    .build()
-                    .expect("Failed to create worker runtime");
-                
-                rt.block_on(async move {
-                    eprintln!("🚀 Python worker {} started!", worker_id);
-                    
-                    // Initialize Python ONCE on this thread
-                    pyo3::prepare_freethreaded_python();
-                    
-                    eprintln!("✅ Python worker {} initialized!", worker_id);
-                    
-                    // Process requests on this dedicated thread
-                    // We DON'T cache TaskLocals - create them per request instead
-                    // This is necessary because each worker has its own runtime
-                    while let Some(req) = rx.recv().await {
-                        let PythonRequest { handler, method, path, query_string, body, response_tx } = req;
-                        let result = handle_python_request_on_worker_no_cache(
-                            handler, method, path, query_string, body
-                        ).await;
-                        let _ = response_tx.send(result);
-                    }
-                    
-                    eprintln!("⚠️  Python worker {} shutting down", worker_id);
-                });
+fn spawn_python_workers(num_workers: usize) -> WorkerPool {
+    tracing::info!(target: "turboapi::worker", "spawning {} Python workers for parallel async execution", num_workers);
+
+    let mut senders = Vec::with_capacity(num_workers);
+    let mut join_handles = Vec::with_capacity(num_workers);
+
+    for worker_id in 0..num_workers {
+        let (tx, mut rx) = mpsc::channel::<WorkerJob>(20000); // INCREASED: 20K capacity for high throughput!
+
+        let join_handle = thread::spawn(move || {
+            // Create single-threaded Tokio runtime for this worker
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create worker runtime");
+
+            let worker_config = *WORKER_CONFIG.get_or_init(WorkerConfig::default);
+            let cached_task_locals = worker_config.cached_task_locals;
+            if cached_task_locals {
+                let _ = pyo3_async_runtimes::tokio::init_with_runtime(&rt);
+            }
+
+            // A `LocalSet` is what lets `spawn_local` (used by the worker
+            // loops below when `local_tasks` is on) hold `!Send` pyo3 state
+            // across an await point — something plain `tokio::spawn` can't
+            // do. Created unconditionally; it's inert when `local_tasks` is
+            // off, since nothing calls `spawn_local` in that case.
+            let local = tokio::task::LocalSet::new();
+
+            local.block_on(&rt, async move {
+                tracing::info!(target: "turboapi::worker", worker_id, "Python worker started");
+
+                // Initialize Python ONCE on this thread
+                pyo3::prepare_freethreaded_python();
+
+                if cached_task_locals {
+                    // Build this worker's TaskLocals once, bound to the
+                    // event loop `init_with_runtime` just registered for
+                    // this thread, instead of letting `into_future` build a
+                    // fresh one per request (see handle_python_request_on_worker_cached).
+                    Python::with_gil(|py| {
+                        match pyo3_async_runtimes::tokio::get_current_locals(py) {
+                            Ok(locals) => WORKER_TASK_LOCALS.with(|cell| *cell.borrow_mut() = Some(locals)),
+                            Err(e) => tracing::error!(target: "turboapi::worker", worker_id, "failed to build cached TaskLocals: {}", e),
+                        }
+                    });
+                }
+
+                tracing::info!(target: "turboapi::worker", worker_id, "Python worker initialized");
+
+                if worker_config.batch_mode {
+                    run_batched_worker_loop(rx, cached_task_locals, worker_config.local_tasks, worker_config.batch_window_micros, worker_config.max_batch).await;
+                } else {
+                    run_worker_loop(rx, cached_task_locals, worker_config.local_tasks).await;
+                }
+
+                tracing::info!(target: "turboapi::worker", worker_id, "Python worker shutting down");
             });
-            
-            tx
+
+            // Finish jobs the loop already handed to `spawn_local` instead of
+            // dropping them along with the `LocalSet`.
+            rt.block_on(local);
+        });
+
+        senders.push(tx);
+        join_handles.push(join_handle);
+    }
+
+    WorkerPool { senders, join_handles }
+}
+
+/// Run `job` to completion and send its result down its own `response_tx`.
+/// Shared by both worker loops below so batching only changes how jobs are
+/// pulled off `rx`, not how each one is handled.
+async fn process_worker_job(job: WorkerJob, cached_task_locals: bool) {
+    match job {
+        WorkerJob::Handler(req) => {
+            let PythonRequest { handler, method, path, query_string, body, response_tx } = req;
+            let result = if cached_task_locals {
+                handle_python_request_on_worker_cached(handler, method, path, query_string, body).await
+            } else {
+                handle_python_request_on_worker_no_cache(handler, method, path, query_string, body).await
+            };
+            let _ = response_tx.send(result);
+        }
+        WorkerJob::Middleware(req) => {
+            let outcome = handle_middleware_request_on_worker(req.callable.clone(), req.stage, &req.method, &req.path, &req.query_string, req.body.clone(), req.status);
+            let _ = req.response_tx.send(outcome);
+        }
+    }
+}
+
+/// Default worker loop: one `recv().await` wakeup per job. Draining ends,
+/// and this loop exits, once every sender for this worker is dropped (see
+/// `WorkerPool::stop`) AND the channel has been drained of whatever was
+/// already queued.
+///
+/// When `local_tasks` is on, each job is handed to `spawn_local` instead of
+/// awaited inline, so this worker can have several requests in flight at
+/// once — one blocked on Python async work doesn't hold up the next job
+/// from being picked off `rx`. Otherwise jobs are fully serialized, same as
+/// before this option existed.
+async fn run_worker_loop(mut rx: mpsc::Receiver<WorkerJob>, cached_task_locals: bool, local_tasks: bool) {
+    while let Some(job) = rx.recv().await {
+        if local_tasks {
+            tokio::task::spawn_local(process_worker_job(job, cached_task_locals));
+        } else {
+            process_worker_job(job, cached_task_locals).await;
+        }
+    }
+}
+
+/// Throttling alternative to `run_worker_loop`: rather than waking up once
+/// per queued job, a worker blocks on `recv().await` for the first job (so
+/// an idle worker still costs no CPU), then drains whatever else is
+/// *already* available via `try_recv` up to `max_batch`, processes the
+/// whole batch, and finally pads the remainder of `batch_window` before
+/// looping back. That last pad is the throttle: it deliberately delays the
+/// next `recv().await` so a burst arriving during it gets drained together
+/// next time, instead of each message re-waking the worker individually.
+///
+/// Invariant: a request's worst-case added latency is one `batch_window`
+/// (if it arrives just after a batch closes) plus however long the worker
+/// takes to get through whatever was already ahead of it — the quantum
+/// plus handler time.
+async fn run_batched_worker_loop(mut rx: mpsc::Receiver<WorkerJob>, cached_task_locals: bool, local_tasks: bool, batch_window_micros: u64, max_batch: usize) {
+    let batch_window = Duration::from_micros(batch_window_micros);
+
+    loop {
+        let first = match rx.recv().await {
+            Some(job) => job,
+            None => break,
+        };
+        let quantum_start = Instant::now();
+
+        let mut batch = Vec::with_capacity(max_batch.min(64));
+        batch.push(first);
+        while batch.len() < max_batch {
+            match rx.try_recv() {
+                Ok(job) => batch.push(job),
+                Err(_) => break, // Empty or disconnected — nothing more to drain right now.
+            }
+        }
+
+        // With `local_tasks` on, the whole batch is fanned out via
+        // `spawn_local` so it runs concurrently instead of one job at a
+        // time, the same tradeoff `run_worker_loop` makes.
+        for job in batch {
+            if local_tasks {
+                tokio::task::spawn_local(process_worker_job(job, cached_task_locals));
+            } else {
+                process_worker_job(job, cached_task_locals).await;
+            }
+        }
+
+        let elapsed = quantum_start.elapsed();
+        if elapsed < batch_window {
+            tokio::time::sleep(batch_window - elapsed).await;
+        }
+    }
+}
+
+/// Owns a Python worker pool's senders plus what's needed to shut it down
+/// deterministically. Previously `spawn_python_workers` returned only the
+/// bare `Vec<Sender>`, so dropping it was the only signal a worker ever
+/// got — nothing ever joined the OS thread or reported whether a worker
+/// had actually stopped, which left threads unjoinable in tests and reload
+/// scenarios.
+struct WorkerPool {
+    senders: Vec<mpsc::Sender<WorkerJob>>,
+    join_handles: Vec<thread::JoinHandle<()>>,
+}
+
+impl WorkerPool {
+    /// Senders for routing `WorkerJob`s to workers. Cheap to clone — one
+    /// clone per connection, same as `run` did directly before this existed.
+    fn senders(&self) -> Vec<mpsc::Sender<WorkerJob>> {
+        self.senders.clone()
+    }
+
+    /// Stop accepting new work (dropping the senders lets each worker's
+    /// `rx.recv()` drain whatever's already queued and then return `None`),
+    /// then join every worker thread, waiting up to `drain_timeout` in
+    /// total across all of them. Returns how many workers did *not* stop
+    /// within that deadline; their threads (and whatever coroutine they
+    /// were mid-await on) are left running rather than forcibly killed,
+    /// since Rust has no safe way to do that.
+    fn stop(self, drain_timeout: Duration) -> usize {
+        drop(self.senders);
+
+        let deadline = Instant::now() + drain_timeout;
+        self.join_handles
+            .into_iter()
+            .map(|handle| join_with_timeout(handle, deadline.saturating_duration_since(Instant::now())))
+            .filter(|stopped| !stopped)
+            .count()
+    }
+}
+
+/// `thread::JoinHandle::join` has no timeout, so run it on a throwaway
+/// watcher thread and wait on that with one instead. If `timeout` passes,
+/// the watcher (and the worker thread it's joining) are left running in
+/// the background rather than blocking shutdown indefinitely.
+fn join_with_timeout(handle: thread::JoinHandle<()>, timeout: Duration) -> bool {
+    let (tx, rx) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        let _ = handle.join();
+        let _ = tx.send(());
+    });
+    rx.recv_timeout(timeout).is_ok()
+}
+
+/// Send a middleware invocation to a worker (hashed by path, same as route
+/// dispatch) and await its decision. Runs off the hyper thread so a slow
+/// middleware callable never blocks connection I/O.
+async fn run_middleware_stage(
+    callable: Arc<PyObject>,
+    stage: MiddlewareStage,
+    method: &str,
+    path: &str,
+    query_string: &str,
+    body: Bytes,
+    status: u16,
+    python_workers: &[mpsc::Sender<WorkerJob>],
+) -> MiddlewareOutcome {
+    if python_workers.is_empty() {
+        return MiddlewareOutcome::Continue;
+    }
+    let worker_id = hash_route_key(path) % python_workers.len();
+    let (resp_tx, resp_rx) = oneshot::channel();
+    let job = WorkerJob::Middleware(MiddlewareRequest {
+        callable,
+        stage,
+        method: method.to_string(),
+        path: path.to_string(),
+        query_string: query_string.to_string(),
+        body,
+        status,
+        response_tx: resp_tx,
+    });
+    if python_workers[worker_id].send(job).await.is_err() {
+        return MiddlewareOutcome::Continue;
+    }
+    resp_rx.await.unwrap_or(MiddlewareOutcome::Continue)
+}
+
+/// Call the given middleware stage's hook method (`request_filter`,
+/// `request_body_filter`, or `response_filter`) if the middleware object
+/// defines it. Missing hooks are treated as "continue".
+///
+/// Convention for the Python-side return value:
+/// - `None` -> continue unchanged
+/// - `bytes`/`str` -> rewrite the body to this value
+/// - `(status, body)` tuple -> stop the pipeline and respond now
+fn handle_middleware_request_on_worker(
+    callable: Arc<PyObject>,
+    stage: MiddlewareStage,
+    method: &str,
+    path: &str,
+    query_string: &str,
+    body: Bytes,
+    status: u16,
+) -> MiddlewareOutcome {
+    let hook_name = match stage {
+        MiddlewareStage::Request => "request_filter",
+        MiddlewareStage::RequestBody => "request_body_filter",
+        MiddlewareStage::Response => "response_filter",
+    };
+
+    Python::attach(|py| {
+        let hook = match callable.getattr(py, hook_name) {
+            Ok(hook) => hook,
+            Err(_) => return MiddlewareOutcome::Continue,
+        };
+
+        let body_py = pyo3::types::PyBytes::new(py, body.as_ref());
+        let result = match hook.call1(py, (method, path, query_string, body_py, status)) {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::warn!(target: "turboapi::middleware", "middleware error in {}: {}", hook_name, e);
+                return MiddlewareOutcome::Continue;
+            }
+        };
+
+        if result.is_none(py) {
+            return MiddlewareOutcome::Continue;
+        }
+        if let Ok((new_status, new_body)) = result.extract::<(u16, Vec<u8>)>(py) {
+            return MiddlewareOutcome::RespondNow { status: new_status, body: Bytes::from(new_body) };
+        }
+        if let Ok(new_body) = result.extract::<Vec<u8>>(py) {
+            return MiddlewareOutcome::Rewrite(Bytes::from(new_body));
+        }
+        if let Ok(new_body) = result.extract::<String>(py) {
+            return MiddlewareOutcome::Rewrite(Bytes::from(new_body));
+        }
+        MiddlewareOutcome::Continue
+    })
+}
+
+// ============================================================================
+// PROMISE - deferred/background task primitive for handlers
+// ============================================================================
+
+static NEXT_PROMISE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Background tasks spawned via `spawn_promise`, keyed by the id handed back
+/// to Python as part of a `Promise`'s acknowledgment body. A `Promise` is
+/// only ever produced by a sync handler on the `call_python_handler_sync_direct`
+/// fast path (see there), which runs directly on the main multi-thread Tokio
+/// runtime rather than on a dedicated worker OS thread, so — unlike
+/// `WORKER_TASK_LOCALS` — this registry is a single global map, not a
+/// per-thread one: `Promise::is_done`/`wait` need to find the task's
+/// `JoinHandle` regardless of which runtime thread happens to service the
+/// polling request.
+static PROMISE_REGISTRY: OnceLock<std::sync::Mutex<StdHashMap<u64, tokio::task::JoinHandle<Result<PyObject, HandlerError>>>>> = OnceLock::new();
+
+fn promise_registry() -> &'static std::sync::Mutex<StdHashMap<u64, tokio::task::JoinHandle<Result<PyObject, HandlerError>>>> {
+    PROMISE_REGISTRY.get_or_init(|| std::sync::Mutex::new(StdHashMap::new()))
+}
+
+/// Deferred-response wrapper: a handler returns `Promise(coroutine)` to hand
+/// the coroutine off to the background-task registry and get an immediate
+/// acknowledgment back instead of the request/response path waiting on it
+/// (see `call_python_handler_sync_direct`). The same object then doubles as
+/// the polling token — `is_done()`/`wait()` look itself up by id in
+/// `PROMISE_REGISTRY`.
+#[pyclass]
+pub struct Promise {
+    coroutine: std::cell::RefCell<Option<PyObject>>,
+    id: u64,
+}
+
+#[pymethods]
+impl Promise {
+    #[new]
+    fn new(coroutine: PyObject) -> Self {
+        Promise {
+            coroutine: std::cell::RefCell::new(Some(coroutine)),
+            id: NEXT_PROMISE_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        }
+    }
+
+    /// Opaque id of the polling token, as returned in the 202 acknowledgment body.
+    #[getter]
+    fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Whether the background task has finished. Reports `true` if there's
+    /// no record of the id — either it was already collected via `wait()`,
+    /// or nothing ever spawned it (the `Promise` was constructed but never
+    /// returned from a handler).
+    fn is_done(&self) -> bool {
+        promise_registry()
+            .lock()
+            .unwrap()
+            .get(&self.id)
+            .map(|handle| handle.is_finished())
+            .unwrap_or(true)
+    }
+
+    /// Block the calling thread until the background task finishes,
+    /// releasing the GIL while waiting (same `allow_threads` pattern as
+    /// `shutdown_workers`) so other requests keep making progress. Returns
+    /// `None` if there's no record of the id, e.g. a prior `wait()` already
+    /// collected it.
+    fn wait(&self, py: Python) -> PyResult<Option<PyObject>> {
+        let handle = promise_registry().lock().unwrap().remove(&self.id);
+        let Some(handle) = handle else {
+            return Ok(None);
+        };
+
+        py.allow_threads(|| {
+            // Block on a fresh runtime on its own thread: the caller may be a
+            // handler already running on a Tokio runtime thread, where
+            // `block_on` would panic.
+            thread::spawn(move || {
+                let rt = tokio::runtime::Runtime::new().unwrap();
+                rt.block_on(handle)
+            })
+            .join()
+            .expect("promise wait thread panicked")
         })
-        .collect()
+        .map_err(|e| PyRuntimeError::new_err(format!("promise task panicked: {}", e)))?
+        .map(Some)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+}
+
+/// Acknowledge a handler's `Promise` return: hand its coroutine to
+/// `spawn_promise` and build the 202 polling-token body immediately, without
+/// waiting on the task at all.
+fn handle_promise(py: Python, promise: &Promise) -> HandlerResponse {
+    let id = spawn_promise(py, promise);
+    HandlerResponse {
+        body: format!(r#"{{"task_id": {}, "status": "accepted"}}"#, id),
+        content_type: None,
+        status: Some(202),
+        headers: Vec::new(),
+    }
+}
+
+/// Convert `promise`'s coroutine into a Rust future and `tokio::spawn` it
+/// onto whichever runtime is driving the calling task — the main
+/// multi-thread runtime built in `run()`, since this is only ever reached
+/// from `call_python_handler_sync_direct`'s connection-handling task — storing
+/// the resulting `JoinHandle` in `PROMISE_REGISTRY` under the promise's id
+/// for a later `is_done`/`wait` to find.
+fn spawn_promise(py: Python, promise: &Promise) -> u64 {
+    let id = promise.id;
+    let Some(coroutine) = promise.coroutine.borrow_mut().take() else {
+        // Already spawned (e.g. the same `Promise` returned twice); nothing
+        // new to do, the existing registry entry still answers is_done/wait.
+        return id;
+    };
+
+    match pyo3_async_runtimes::tokio::into_future(coroutine.bind(py).clone()) {
+        Ok(future) => {
+            let handle = tokio::task::spawn(async move {
+                future
+                    .await
+                    .map_err(|e| HandlerError::Python(format!("Promise execution error: {}", e)))
+            });
+            promise_registry().lock().unwrap().insert(id, handle);
+        }
+        Err(e) => {
+            tracing::error!(target: "turboapi::promise", id, "failed to convert promise coroutine to a future: {}", e);
+        }
+    }
+
+    id
+}
+
+/// What calling a handler produced, before any `.await` happens.
+///
+/// There's no `Promise`/deferred variant here: every handler reaching this
+/// function was registered as a coroutine function (`HandlerMetadata::is_async`,
+/// checked once in `add_route`), which is the only kind routed through the
+/// worker channel that calls this — see `handle_request`'s async/sync split.
+/// A sync handler returning a `Promise` is instead caught directly in
+/// `call_python_handler_sync_direct`, the only path that ever calls one.
+enum HandlerDispatch {
+    /// Handler was a coroutine function; here's the coroutine it returned.
+    Async(PyObject),
+    /// Handler was a plain function; here's its already-serialized result.
+    Sync(PyObject),
+}
+
+/// Calls `handler` and classifies what it produced. Shared by both
+/// `handle_python_request_on_worker_no_cache` and
+/// `handle_python_request_on_worker_cached` below; they only differ in how
+/// `HandlerDispatch::Async`'s coroutine is awaited.
+fn dispatch_python_handler(py: Python, handler: &Handler) -> Result<HandlerDispatch, HandlerError> {
+    // Get cached modules
+    let json_module = CACHED_JSON_MODULE.get_or_init(|| {
+        py.import("json").unwrap().into()
+    });
+
+    // Check if async
+    let inspect_module = py.import("inspect").unwrap();
+    let is_async = inspect_module
+        .getattr("iscoroutinefunction").unwrap()
+        .call1((handler.clone_ref(py),)).unwrap()
+        .extract::<bool>().unwrap();
+
+    if is_async {
+        // Call handler to get coroutine (NO kwargs!)
+        let coroutine = handler.call0(py).unwrap();
+        let coroutine_obj: PyObject = coroutine.into();
+
+        // The minimal GIL-bound work for the async path ends here — hand
+        // the GIL back before `into_future`/`.await` take over below.
+        a_sync_allow_threads!(py);
+
+        Ok(HandlerDispatch::Async(coroutine_obj))
+    } else {
+        // Call sync handler directly (NO kwargs!)
+        let result = handler.call0(py)
+            .map_err(|e| classify_py_err(py, &e))?;
+
+        // Extract or serialize result
+        match result.extract::<String>(py) {
+            Ok(json_str) => Ok(HandlerDispatch::Sync(PyString::new(py, &json_str).into())),
+            Err(_) => {
+                let json_dumps = json_module.getattr(py, "dumps").unwrap();
+                let json_str = json_dumps.call1(py, (result,))
+                    .map_err(|e| HandlerError::Serialization(e.to_string()))?;
+                Ok(HandlerDispatch::Sync(json_str.into()))
+            }
+        }
+    }
 }
 
 /// Handle Python request WITHOUT cached TaskLocals (for multi-worker)
 /// Each worker creates its own TaskLocals per request
 async fn handle_python_request_on_worker_no_cache(
     handler: Handler,
-    method: String,
-    path: String,
-    query_string: String,
-    body: Bytes,
-) -> Result<String, String> {
-    // Check if handler is async
-    let (is_async, coroutine_or_result) = Python::with_gil(|py| {
-        // Get cached modules
-        let json_module = CACHED_JSON_MODULE.get_or_init(|| {
-            py.import("json").unwrap().into()
-        });
-        
-        // Check if async
-        let inspect_module = py.import("inspect").unwrap();
-        let is_async = inspect_module
-            .getattr("iscoroutinefunction").unwrap()
-            .call1((handler.clone_ref(py),)).unwrap()
-            .extract::<bool>().unwrap();
-        
-        if is_async {
-            // Call handler to get coroutine (NO kwargs!)
-            let coroutine = handler.call0(py).unwrap();
-            let coroutine_obj: PyObject = coroutine.into();
-            Ok::<_, String>((true, Some(coroutine_obj)))
-        } else {
-            // Call sync handler directly (NO kwargs!)
-            let result = handler.call0(py)
-                .map_err(|e| format!("Python error: {}", e))?;
-            
-            // Extract or serialize result
-            match result.extract::<String>(py) {
-                Ok(json_str) => Ok((false, Some(PyString::new(py, &json_str).into()))),
-                Err(_) => {
-                    let json_dumps = json_module.getattr(py, "dumps").unwrap();
-                    let json_str = json_dumps.call1(py, (result,))
-                        .map_err(|e| format!("JSON error: {}", e))?;
-                    Ok((false, Some(json_str.into())))
-                }
-            }
+    _method: String,
+    _path: String,
+    _query_string: String,
+    _body: Bytes,
+) -> Result<HandlerResponse, HandlerError> {
+    let dispatch = Python::with_gil(|py| dispatch_python_handler(py, &handler))?;
+
+    match dispatch {
+        HandlerDispatch::Async(coroutine) => {
+            // Convert to Rust Future (creates TaskLocals internally)
+            let rust_future = Python::with_gil(|py| {
+                pyo3_async_runtimes::tokio::into_future(coroutine.bind(py).clone())
+            }).map_err(|e| HandlerError::Python(format!("Future conversion error: {}", e)))?;
+
+            // Await on THIS thread's runtime. No GIL is held across this point —
+            // `into_future` only re-acquires it itself while polling the
+            // coroutine — so a handler awaiting blocking work in here no longer
+            // stalls every other worker on this free-threaded interpreter.
+            let result = rust_future.await
+                .map_err(|e| Python::with_gil(|py| classify_py_err(py, &e)))?;
+
+            // Extract result
+            Python::with_gil(|py| {
+                result.extract::<String>(py)
+                    .map_err(|e| HandlerError::Serialization(format!("Result extraction error: {}", e)))
+                    .map(HandlerResponse::json)
+            })
+        }
+        HandlerDispatch::Sync(result_obj) => {
+            Python::with_gil(|py| {
+                result_obj.extract::<String>(py)
+                    .map_err(|e| HandlerError::Serialization(format!("Result extraction error: {}", e)))
+                    .map(HandlerResponse::json)
+            })
+        }
+    }
+}
+
+/// Same dispatch as `handle_python_request_on_worker_no_cache`, but awaits
+/// the coroutine against this worker's cached `TaskLocals`
+/// (`WORKER_TASK_LOCALS`, built once in `spawn_python_workers`) via
+/// `into_future_with_locals` instead of letting `into_future` build a fresh
+/// event-loop reference and context on every call. Selected per-worker by
+/// `configure_worker_runtime(cached_task_locals=True)`.
+async fn handle_python_request_on_worker_cached(
+    handler: Handler,
+    _method: String,
+    _path: String,
+    _query_string: String,
+    _body: Bytes,
+) -> Result<HandlerResponse, HandlerError> {
+    let dispatch = Python::with_gil(|py| dispatch_python_handler(py, &handler))?;
+
+    match dispatch {
+        HandlerDispatch::Async(coroutine) => {
+            let rust_future = WORKER_TASK_LOCALS.with(|cell| {
+                let locals = cell.borrow();
+                let locals = locals.as_ref().expect(
+                    "cached TaskLocals not initialized for this worker; get_current_locals failed at worker startup",
+                );
+                Python::with_gil(|py| {
+                    pyo3_async_runtimes::tokio::into_future_with_locals(locals, coroutine.bind(py).clone())
+                })
+            }).map_err(|e| HandlerError::Python(format!("Future conversion error: {}", e)))?;
+
+            // Same GIL-free await as the no-cache path; only how `rust_future`
+            // was built above differs.
+            let result = rust_future.await
+                .map_err(|e| Python::with_gil(|py| classify_py_err(py, &e)))?;
+
+            Python::with_gil(|py| {
+                result.extract::<String>(py)
+                    .map_err(|e| HandlerError::Serialization(format!("Result extraction error: {}", e)))
+                    .map(HandlerResponse::json)
+            })
+        }
+        HandlerDispatch::Sync(result_obj) => {
+            Python::with_gil(|py| {
+                result_obj.extract::<String>(py)
+                    .map_err(|e| HandlerError::Serialization(format!("Result extraction error: {}", e)))
+                    .map(HandlerResponse::json)
+            })
         }
-    }).map_err(|e: String| e)?;
-    
-    if is_async {
-        // Async path - use pyo3_async_runtimes WITHOUT cached TaskLocals
-        let coroutine = coroutine_or_result.unwrap();
-        
-        // Convert to Rust Future (creates TaskLocals internally)
-        let rust_future = Python::with_gil(|py| {
-            pyo3_async_runtimes::tokio::into_future(coroutine.bind(py).clone())
-        }).map_err(|e| format!("Future conversion error: {}", e))?;
-        
-        // Await on THIS thread's runtime
-        let result = rust_future.await
-            .map_err(|e| format!("Async execution error: {}", e))?;
-        
-        // Extract result
-        Python::with_gil(|py| {
-            result.extract::<String>(py)
-                .map_err(|e| format!("Result extraction error: {}", e))
-        })
-    } else {
-        // Sync path - result already extracted
-        let result_obj = coroutine_or_result.unwrap();
-        Python::with_gil(|py| {
-            result_obj.extract::<String>(py)
-                .map_err(|e| format!("Result extraction error: {}", e))
-        })
     }
 }